@@ -0,0 +1,72 @@
+use super::PacketHeader;
+
+/// The payload of a HCI command, as sent to the CPU2 coprocessor.
+///
+/// `payload` is sized to the largest command payload the TL protocol allows
+/// (a single byte carries its length), mirroring the flexible array member
+/// the C transport layer uses here; most commands only use a handful of
+/// those bytes.
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+pub struct Cmd {
+    pub cmd_code: u16,
+    pub payload_len: u8,
+    pub payload: [u8; 255],
+}
+
+impl Default for Cmd {
+    fn default() -> Self {
+        Self {
+            cmd_code: 0,
+            payload_len: 0,
+            payload: [0; 255],
+        }
+    }
+}
+
+#[derive(Copy, Clone, Default)]
+#[repr(C, packed)]
+pub struct CmdSerial {
+    pub kind: u8,
+    pub cmd: Cmd,
+}
+
+/// This format shall be used for all commands (BLE, system and MAC) sent to the CPU2.
+#[derive(Copy, Clone, Default)]
+#[repr(C, packed)]
+pub struct CmdPacket {
+    pub header: PacketHeader,
+    pub cmd_serial: CmdSerial,
+}
+
+/// The serialized form of a HCI ACL data packet, used to carry L2CAP data between the
+/// host and the controller outside of the regular event/command channel.
+///
+/// `acl_data` is sized like [`Cmd::payload`], 255 bytes. `length` is a `u16`
+/// and can describe more than that; a longer payload does not fit here.
+#[derive(Copy, Clone)]
+#[repr(C, packed)]
+pub struct AclDataSerial {
+    pub kind: u8,
+    pub handle: u16,
+    pub length: u16,
+    pub acl_data: [u8; 255],
+}
+
+impl Default for AclDataSerial {
+    fn default() -> Self {
+        Self {
+            kind: 0,
+            handle: 0,
+            length: 0,
+            acl_data: [0; 255],
+        }
+    }
+}
+
+#[derive(Copy, Clone, Default)]
+#[repr(C, packed)]
+pub struct AclDataPacket {
+    pub header: PacketHeader,
+    pub acl_data_serial: AclDataSerial,
+}
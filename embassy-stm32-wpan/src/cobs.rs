@@ -0,0 +1,190 @@
+//! Consistent Overhead Byte Stuffing (COBS) framing.
+//!
+//! Every encoded frame is terminated by a single `0x00` delimiter that never
+//! appears anywhere else in the frame, which makes a raw byte stream
+//! self-synchronizing again after any glitch: a receiver that joins mid-frame
+//! or loses bytes only has to resync on the next `0x00` rather than resync
+//! the whole link.
+
+const DELIMITER: u8 = 0x00;
+
+/// Encodes `data` as a single COBS frame, including the trailing `0x00`
+/// delimiter, into `out`. Returns the number of bytes written, or `None` if
+/// `out` is too small to hold the encoded frame.
+pub fn encode(data: &[u8], out: &mut [u8]) -> Option<usize> {
+    if out.is_empty() {
+        return None;
+    }
+
+    let mut out_idx = 1;
+    let mut code_idx = 0;
+    let mut code = 1u8;
+
+    for &byte in data {
+        if byte == DELIMITER {
+            out[code_idx] = code;
+            code_idx = out_idx;
+            out_idx += 1;
+            code = 1;
+        } else {
+            if out_idx >= out.len() {
+                return None;
+            }
+            out[out_idx] = byte;
+            out_idx += 1;
+            code += 1;
+
+            if code == 0xFF {
+                out[code_idx] = code;
+                code_idx = out_idx;
+                out_idx += 1;
+                code = 1;
+            }
+        }
+
+        if code_idx >= out.len() || out_idx > out.len() {
+            return None;
+        }
+    }
+
+    out[code_idx] = code;
+    if out_idx >= out.len() {
+        return None;
+    }
+    out[out_idx] = DELIMITER;
+    out_idx += 1;
+
+    Some(out_idx)
+}
+
+/// Decodes a single COBS frame (with or without its trailing `0x00`
+/// delimiter, which is ignored if present) into `out`. Returns the number of
+/// decoded bytes, or `None` if `frame` is malformed or `out` is too small.
+pub fn decode(frame: &[u8], out: &mut [u8]) -> Option<usize> {
+    let frame = match frame.split_last() {
+        Some((&DELIMITER, rest)) => rest,
+        _ => frame,
+    };
+
+    let mut in_idx = 0;
+    let mut out_idx = 0;
+
+    while in_idx < frame.len() {
+        let code = frame[in_idx] as usize;
+        if code == 0 {
+            return None;
+        }
+        in_idx += 1;
+
+        for _ in 1..code {
+            if in_idx >= frame.len() || out_idx >= out.len() {
+                return None;
+            }
+            out[out_idx] = frame[in_idx];
+            out_idx += 1;
+            in_idx += 1;
+        }
+
+        if code != 0xFF && in_idx < frame.len() {
+            if out_idx >= out.len() {
+                return None;
+            }
+            out[out_idx] = DELIMITER;
+            out_idx += 1;
+        }
+    }
+
+    Some(out_idx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip(data: &[u8]) {
+        let mut encoded = [0u8; 600];
+        let encoded_len = encode(data, &mut encoded).expect("encode failed");
+
+        let mut decoded = [0u8; 600];
+        let decoded_len = decode(&encoded[..encoded_len], &mut decoded).expect("decode failed");
+
+        assert_eq!(&decoded[..decoded_len], data);
+
+        // The trailing delimiter is optional on decode.
+        let without_delim = &encoded[..encoded_len - 1];
+        let decoded_len = decode(without_delim, &mut decoded).expect("decode without delimiter failed");
+        assert_eq!(&decoded[..decoded_len], data);
+    }
+
+    #[test]
+    fn roundtrips_empty() {
+        roundtrip(&[]);
+    }
+
+    #[test]
+    fn roundtrips_no_zeros() {
+        roundtrip(&[1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn roundtrips_single_zero() {
+        roundtrip(&[0]);
+    }
+
+    #[test]
+    fn roundtrips_leading_and_trailing_zeros() {
+        roundtrip(&[0, 1, 2, 0]);
+    }
+
+    #[test]
+    fn roundtrips_consecutive_zeros() {
+        roundtrip(&[1, 0, 0, 0, 2]);
+    }
+
+    #[test]
+    fn roundtrips_254_byte_run_without_zero() {
+        // Exercises the 0xFF code-block boundary.
+        roundtrip(&[0xAA; 254]);
+    }
+
+    #[test]
+    fn roundtrips_255_byte_run_without_zero() {
+        roundtrip(&[0xAA; 255]);
+    }
+
+    #[test]
+    fn roundtrips_large_mixed_data() {
+        let data: [u8; 300] = core::array::from_fn(|i| (i % 7) as u8);
+        roundtrip(&data);
+    }
+
+    #[test]
+    fn encode_fails_when_out_too_small() {
+        let data = [1, 2, 3];
+        let mut out = [0u8; 3];
+        assert_eq!(encode(&data, &mut out), None);
+    }
+
+    #[test]
+    fn decode_fails_on_zero_code_byte() {
+        // A `0x00` code byte is never valid mid-frame.
+        let mut out = [0u8; 16];
+        assert_eq!(decode(&[0x00, 0x01], &mut out), None);
+    }
+
+    #[test]
+    fn decode_fails_on_truncated_frame() {
+        // Code byte claims 4 following bytes, but only 1 is present.
+        let mut out = [0u8; 16];
+        assert_eq!(decode(&[0x05, 0xAA], &mut out), None);
+    }
+
+    #[test]
+    fn decode_fails_when_out_too_small() {
+        let mut encoded = [0u8; 16];
+        let len = encode(&[1, 2, 3], &mut encoded).unwrap();
+
+        let mut out = [0u8; 2];
+        assert_eq!(decode(&encoded[..len], &mut out), None);
+    }
+}
@@ -0,0 +1,27 @@
+use core::convert::TryFrom;
+
+/// Identifies the kind of packet carried by the first byte of [`crate::evt::EvtSerial`]
+/// and [`crate::cmd::CmdSerial`], i.e. the HCI H4-style packet indicator.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TlPacketType {
+    HciCmd = 0x01,
+    AclData = 0x02,
+    HciEvt = 0x04,
+    SysCmd = 0x10,
+    SysEvt = 0x12,
+}
+
+impl TryFrom<u8> for TlPacketType {
+    type Error = ();
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0x01 => Ok(TlPacketType::HciCmd),
+            0x02 => Ok(TlPacketType::AclData),
+            0x04 => Ok(TlPacketType::HciEvt),
+            0x10 => Ok(TlPacketType::SysCmd),
+            0x12 => Ok(TlPacketType::SysEvt),
+            _ => Err(()),
+        }
+    }
+}
@@ -0,0 +1,128 @@
+//! FUS (Firmware Upgrade Service): lets the application stage a new wireless
+//! coprocessor (CPU2) firmware image in flash and drive CPU2 through
+//! reflashing itself from it, without an external programmer.
+//!
+//! Layered directly on [`crate::sys::Sys`] and [`crate::shci`]: FUS control
+//! commands are just system commands carrying a [`ShciOpcode`], and their
+//! response is a single status byte carried back as a system command
+//! complete event.
+
+use core::convert::TryFrom;
+
+use embassy_time::{Duration, Timer};
+
+use crate::shci::{ShciFusStatus, ShciOpcode};
+use crate::sys::Sys;
+use crate::tables::DeviceInfoTable;
+
+/// A `FUS_GET_STATE` state byte with bit 7 set indicates an error; the
+/// low 7 bits then carry a [`ShciFusStatus`].
+const FUS_STATE_ERROR_BIT: u8 = 0x80;
+/// `FUS_GET_STATE` reports this once FUS has handed control back to the
+/// wireless stack (or there is no upgrade in progress).
+const FUS_STATE_IDLE: u8 = 0x00;
+/// Delay between `FUS_GET_STATE` polls while an upgrade is in flight, so
+/// [`Fus::upgrade`] doesn't hammer the system channel for the tens of
+/// seconds a reflash can take.
+const FUS_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Progress reported back to the caller while an upgrade is in flight.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FusProgress {
+    /// FUS is still running the upgrade; argument is the last `FUS_GET_STATE`
+    /// state byte.
+    InProgress(u8),
+    /// The upgrade completed and FUS handed control back to the wireless
+    /// stack.
+    Done,
+}
+
+/// Errors that can abort a firmware upgrade.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FusError {
+    /// A `FUS_GET_STATE` response carried no payload at all.
+    EmptyState,
+    /// A `FUS_GET_STATE` response carried a status byte this driver doesn't
+    /// recognize.
+    UnknownStatus(u8),
+    /// FUS reported a failure status for the requested operation.
+    Failed(ShciFusStatus),
+}
+
+/// Drives the FUS control state machine over the system channel.
+pub struct Fus<'d> {
+    sys: &'d Sys,
+}
+
+impl<'d> Fus<'d> {
+    pub fn new(sys: &'d Sys) -> Self {
+        Self { sys }
+    }
+
+    /// the `DeviceInfoTable` populated by CPU2/FUS at startup, giving the
+    /// currently installed FUS and wireless-stack versions.
+    pub fn device_info(&self) -> &'static DeviceInfoTable {
+        unsafe { &*(*crate::TL_REF_TABLE.as_ptr()).device_info_table }
+    }
+
+    /// issues `FUS_GET_STATE` and returns the raw state byte from its
+    /// response.
+    pub async fn get_state(&self) -> Result<u8, FusError> {
+        self.sys.write(ShciOpcode::FusGetState as u16, &[]);
+        let evt = self.sys.read().await;
+        evt.payload().first().copied().ok_or(FusError::EmptyState)
+    }
+
+    /// commands CPU2 to reflash itself (`FUS_FW_UPGRADE`) from the image
+    /// already staged at `flash_slot_addr` by the application's own flash
+    /// driver, then polls `FUS_GET_STATE` until FUS reports completion or an
+    /// error.
+    ///
+    /// `on_progress` is called with [`FusProgress::InProgress`] after every
+    /// poll that finds FUS busy, and once with [`FusProgress::Done`] when it
+    /// returns to idle; idle polls before the upgrade starts and error
+    /// results do not call it. The upgrade itself can take tens of seconds,
+    /// so polls are spaced [`FUS_POLL_INTERVAL`] apart.
+    pub async fn upgrade(&self, flash_slot_addr: u32, mut on_progress: impl FnMut(FusProgress)) -> Result<(), FusError> {
+        self.sys
+            .write(ShciOpcode::FusFwUpgrade as u16, &flash_slot_addr.to_le_bytes());
+
+        // FUS_GET_STATE reports FUS_STATE_IDLE both before FUS has picked up
+        // the FW_UPGRADE command and once it's done; only the latter counts
+        // as `Done`, so track whether we've actually seen it leave idle.
+        let mut started = false;
+
+        loop {
+            Timer::after(FUS_POLL_INTERVAL).await;
+
+            let state = self.get_state().await?;
+
+            if state & FUS_STATE_ERROR_BIT != 0 {
+                let status = ShciFusStatus::try_from(state & !FUS_STATE_ERROR_BIT)
+                    .map_err(|_| FusError::UnknownStatus(state))?;
+                return Err(FusError::Failed(status));
+            }
+
+            if state == FUS_STATE_IDLE {
+                if started {
+                    on_progress(FusProgress::Done);
+                    return Ok(());
+                }
+            } else {
+                started = true;
+                on_progress(FusProgress::InProgress(state));
+            }
+        }
+    }
+
+    /// erases the currently staged wireless stack image (`FUS_FW_DELETE`).
+    pub fn delete(&self) {
+        self.sys.write(ShciOpcode::FusFwDelete as u16, &[]);
+    }
+
+    /// hands control back to the wireless stack once FUS has finished
+    /// (`FUS_START_WS`).
+    pub fn start_ws(&self) {
+        self.sys.write(ShciOpcode::FusStartWs as u16, &[]);
+    }
+}
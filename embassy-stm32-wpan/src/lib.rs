@@ -27,12 +27,19 @@ use unsafe_linked_list::LinkedListNode;
 pub mod ble;
 pub mod channels;
 pub mod cmd;
+pub mod cobs;
 pub mod consts;
 pub mod evt;
+pub mod fus;
+#[cfg(feature = "mac")]
+pub mod mac;
+#[cfg(feature = "mac")]
+pub mod mac_driver;
 pub mod mm;
 pub mod shci;
 pub mod sys;
 pub mod tables;
+pub mod transport;
 pub mod unsafe_linked_list;
 
 #[link_section = "TL_REF_TABLE"]
@@ -72,7 +79,10 @@ static mut TRACES_EVT_QUEUE: MaybeUninit<LinkedListNode> = MaybeUninit::uninit()
 type PacketHeader = LinkedListNode;
 
 const TL_PACKET_HEADER_SIZE: usize = core::mem::size_of::<PacketHeader>();
-const TL_EVT_HEADER_SIZE: usize = 3;
+pub(crate) const TL_EVT_HEADER_SIZE: usize = 3;
+/// Size of a serialized [`cmd::CmdSerial`] up to (not including) its payload:
+/// `kind` + `cmd_code` + `payload_len`.
+pub(crate) const TL_CMD_HEADER_SIZE: usize = 4;
 const TL_CS_EVT_SIZE: usize = core::mem::size_of::<evt::CsEvt>();
 
 #[link_section = "MB_MEM2"]
@@ -88,31 +98,75 @@ static mut SYSTEM_EVT_QUEUE: MaybeUninit<LinkedListNode> = MaybeUninit::uninit()
 #[link_section = "MB_MEM2"]
 pub static mut SYS_CMD_BUF: MaybeUninit<CmdPacket> = MaybeUninit::uninit();
 
-/**
- * Queue length of BLE Event
- * This parameter defines the number of asynchronous events that can be stored in the HCI layer before
- * being reported to the application. When a command is sent to the BLE core coprocessor, the HCI layer
- * is waiting for the event with the Num_HCI_Command_Packets set to 1. The receive queue shall be large
- * enough to store all asynchronous events received in between.
- * When CFG_TLBLE_MOST_EVENT_PAYLOAD_SIZE is set to 27, this allow to store three 255 bytes long asynchronous events
- * between the HCI command and its event.
- * This parameter depends on the value given to CFG_TLBLE_MOST_EVENT_PAYLOAD_SIZE. When the queue size is to small,
- * the system may hang if the queue is full with asynchronous events and the HCI layer is still waiting
- * for a CC/CS event, In that case, the notification TL_BLE_HCI_ToNot() is called to indicate
- * to the application a HCI command did not receive its command event within 30s (Default HCI Timeout).
- */
-const CFG_TLBLE_EVT_QUEUE_LENGTH: usize = 5;
-const CFG_TLBLE_MOST_EVENT_PAYLOAD_SIZE: usize = 255;
-const TL_BLE_EVENT_FRAME_SIZE: usize = TL_EVT_HEADER_SIZE + CFG_TLBLE_MOST_EVENT_PAYLOAD_SIZE;
-
 const fn divc(x: usize, y: usize) -> usize {
     ((x) + (y) - 1) / (y)
 }
 
-const POOL_SIZE: usize = CFG_TLBLE_EVT_QUEUE_LENGTH * 4 * divc(TL_PACKET_HEADER_SIZE + TL_BLE_EVENT_FRAME_SIZE, 4);
+/// Size, in bytes, of the `MB_MEM2` event pool arena needed to hold
+/// `evt_queue_length` asynchronous events of up to `most_event_payload_size`
+/// bytes each.
+///
+/// This parameter defines the number of asynchronous events that can be stored in the HCI layer before
+/// being reported to the application. When a command is sent to the BLE core coprocessor, the HCI layer
+/// is waiting for the event with the Num_HCI_Command_Packets set to 1. The receive queue shall be large
+/// enough to store all asynchronous events received in between.
+/// When `most_event_payload_size` is set to 27, this allows storing three 255 bytes long asynchronous
+/// events between the HCI command and its event. When the queue size is too small, the system may hang
+/// if the queue is full with asynchronous events and the HCI layer is still waiting for a CC/CS event;
+/// in that case the notification `TL_BLE_HCI_ToNot()` is called to indicate to the application that a
+/// HCI command did not receive its command event within 30s (default HCI timeout).
+pub const fn pool_size(evt_queue_length: usize, most_event_payload_size: usize) -> usize {
+    let evt_frame_size = TL_EVT_HEADER_SIZE + most_event_payload_size;
+    evt_queue_length * 4 * divc(TL_PACKET_HEADER_SIZE + evt_frame_size, 4)
+}
 
-#[link_section = "MB_MEM2"]
-static mut EVT_POOL: MaybeUninit<[u8; POOL_SIZE]> = MaybeUninit::uninit();
+/// The event-queue depth and pool sizing used before this was configurable:
+/// a 5-entry event queue of up to 255-byte events, and a 32-entry `EvtBox`
+/// channel.
+pub const DEFAULT_POOL_SIZE: usize = pool_size(5, 255);
+pub const DEFAULT_EVT_CHANNEL_CAPACITY: usize = 32;
+
+/// `MB_MEM2` event pool and `EvtBox` channel, sized to taste: a deployment
+/// handling many concurrent connections can enlarge `POOL_SIZE` (computed
+/// via [`pool_size`]) and `EVT_CHANNEL_CAPACITY`, while a constrained build
+/// can shrink them. Declare one as a `static` in the `MB_MEM2` section and
+/// pass it to [`TlMbox::init`]:
+///
+/// ```ignore
+/// const POOL_SIZE: usize = embassy_stm32_wpan::pool_size(8, 255);
+/// #[link_section = "MB_MEM2"]
+/// static mut MAILBOX: embassy_stm32_wpan::Mailbox<POOL_SIZE, 64> = embassy_stm32_wpan::Mailbox::new();
+/// ```
+///
+/// `POOL_SIZE` and `EVT_CHANNEL_CAPACITY` size two different things: `evt_pool`
+/// is a scratch arena TL reuses as events are copied out to `evt_channel`, so
+/// it only needs room for `evt_queue_length` in-flight events, not one slot
+/// per `EVT_CHANNEL_CAPACITY` entry — always compute `POOL_SIZE` with
+/// [`pool_size`] for the `evt_queue_length`/`most_event_payload_size` the
+/// deployment actually expects in flight, rather than deriving it from
+/// `EVT_CHANNEL_CAPACITY`.
+pub struct Mailbox<
+    const POOL_SIZE: usize = DEFAULT_POOL_SIZE,
+    const EVT_CHANNEL_CAPACITY: usize = DEFAULT_EVT_CHANNEL_CAPACITY,
+> {
+    evt_pool: MaybeUninit<[u8; POOL_SIZE]>,
+    evt_channel: Channel<CriticalSectionRawMutex, EvtBox, EVT_CHANNEL_CAPACITY>,
+}
+
+impl<const POOL_SIZE: usize, const EVT_CHANNEL_CAPACITY: usize> Mailbox<POOL_SIZE, EVT_CHANNEL_CAPACITY> {
+    pub const fn new() -> Self {
+        Self {
+            evt_pool: MaybeUninit::uninit(),
+            evt_channel: Channel::new(),
+        }
+    }
+}
+
+impl<const POOL_SIZE: usize, const EVT_CHANNEL_CAPACITY: usize> Default for Mailbox<POOL_SIZE, EVT_CHANNEL_CAPACITY> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 #[link_section = "MB_MEM2"]
 static mut SYS_SPARE_EVT_BUF: MaybeUninit<[u8; TL_PACKET_HEADER_SIZE + TL_EVT_HEADER_SIZE + 255]> =
@@ -131,34 +185,50 @@ static mut HCI_ACL_DATA_BUFFER: MaybeUninit<[u8; TL_PACKET_HEADER_SIZE + 5 + 251
 
 // TODO: remove these items
 
-#[allow(dead_code)]
-/// current event that is produced during IPCC IRQ handler execution
-/// on SYS channel
-static EVT_CHANNEL: Channel<CriticalSectionRawMutex, EvtBox, 32> = Channel::new();
-
 #[allow(dead_code)]
 /// last received Command Complete event
 static LAST_CC_EVT: Signal<CriticalSectionRawMutex, CcEvt> = Signal::new();
 
 static STATE: Signal<CriticalSectionRawMutex, ()> = Signal::new();
 
-pub struct TlMbox<'d> {
+pub struct TlMbox<'d, const EVT_CHANNEL_CAPACITY: usize = DEFAULT_EVT_CHANNEL_CAPACITY> {
     _ipcc: PeripheralRef<'d, IPCC>,
+    evt_channel: &'d Channel<CriticalSectionRawMutex, EvtBox, EVT_CHANNEL_CAPACITY>,
 
     pub sys_subsystem: Sys,
     pub mm_subsystem: MemoryManager,
     pub ble_subsystem: Ble,
+    #[cfg(feature = "mac")]
+    pub mac_subsystem: mac::Mac,
 }
 
-impl<'d> TlMbox<'d> {
-    pub fn init(
+impl<'d, const EVT_CHANNEL_CAPACITY: usize> TlMbox<'d, EVT_CHANNEL_CAPACITY> {
+    pub fn init<const POOL_SIZE: usize>(
         ipcc: impl Peripheral<P = IPCC> + 'd,
         _irqs: impl interrupt::typelevel::Binding<interrupt::typelevel::IPCC_C1_RX, ReceiveInterruptHandler>
             + interrupt::typelevel::Binding<interrupt::typelevel::IPCC_C1_TX, TransmitInterruptHandler>,
         config: Config,
+        mailbox: &'d mut Mailbox<POOL_SIZE, EVT_CHANNEL_CAPACITY>,
     ) -> Self {
         into_ref!(ipcc);
 
+        const {
+            // This only catches a POOL_SIZE that can't even hold one
+            // worst-case (255-byte) event; it can't check POOL_SIZE against
+            // the evt_queue_length/most_event_payload_size a caller actually
+            // chose; `Mailbox::evt_pool` is a scratch arena TL reuses as
+            // events are copied out to `Mailbox::evt_channel`, not one slot per
+            // `EVT_CHANNEL_CAPACITY` entry, so POOL_SIZE must always be
+            // computed with `pool_size(evt_queue_length,
+            // most_event_payload_size)` for the real depth/size the
+            // deployment expects in flight at once, not derived from
+            // EVT_CHANNEL_CAPACITY here.
+            assert!(
+                POOL_SIZE >= pool_size(1, 255),
+                "mailbox event pool is too small to hold even one worst-case (255-byte) event; size POOL_SIZE with `pool_size(evt_queue_length, most_event_payload_size)`"
+            );
+        }
+
         unsafe {
             TL_REF_TABLE.as_mut_ptr().write_volatile(RefTable {
                 device_info_table: TL_DEVICE_INFO_TABLE.as_ptr(),
@@ -205,7 +275,8 @@ impl<'d> TlMbox<'d> {
             //                .as_mut_ptr()
             //                .write_volatile(MaybeUninit::zeroed().assume_init());
 
-            EVT_POOL
+            mailbox
+                .evt_pool
                 .as_mut_ptr()
                 .write_volatile(MaybeUninit::zeroed().assume_init());
             SYS_SPARE_EVT_BUF
@@ -235,6 +306,8 @@ impl<'d> TlMbox<'d> {
         let sys = sys::Sys::new();
         let ble = ble::Ble::new();
         let mm = mm::MemoryManager::new();
+        #[cfg(feature = "mac")]
+        let mac = mac::Mac::new();
 
         // enable interrupts
         interrupt::typelevel::IPCC_C1_RX::unpend();
@@ -247,9 +320,25 @@ impl<'d> TlMbox<'d> {
 
         Self {
             _ipcc: ipcc,
+            evt_channel: &mailbox.evt_channel,
             sys_subsystem: sys,
             ble_subsystem: ble,
             mm_subsystem: mm,
+            #[cfg(feature = "mac")]
+            mac_subsystem: mac,
         }
     }
+
+    /// Returns a [`transport::HostTransport`] that bridges events drained
+    /// from the HCI event queue to a host over a COBS-framed byte link, and
+    /// decodes commands coming back from the host.
+    pub fn host_transport(&self) -> transport::HostTransport<'d, EVT_CHANNEL_CAPACITY> {
+        transport::HostTransport::new(self.evt_channel.receiver())
+    }
+
+    /// Returns a [`fus::Fus`] to query or replace the CPU2 wireless stack
+    /// firmware through the Firmware Upgrade Service.
+    pub fn fus(&self) -> fus::Fus<'_> {
+        fus::Fus::new(&self.sys_subsystem)
+    }
 }
@@ -0,0 +1,96 @@
+//! MAC 802.15.4 subsystem: the command/event path used to drive the radio in
+//! raw 802.15.4 mode through CPU2.
+//!
+//! Mirrors [`crate::ble::Ble`] and [`crate::sys::Sys`]: its own `MB_MEM2`
+//! command buffer, its own IPCC channel, and a `write`/`read` pair that
+//! drains the MAC event queue (data indications, data/association
+//! confirmations, ...) into [`EvtBox`]es on [`MAC_EVT_CHANNEL`].
+//!
+//! Gated behind the `mac` feature so BLE-only builds don't carry the extra
+//! `MB_MEM2` buffers or IPCC channel.
+
+use core::mem::MaybeUninit;
+
+use embassy_stm32::ipcc::Ipcc;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::channel::Channel;
+
+use crate::cmd::CmdPacket;
+use crate::evt::EvtBox;
+use crate::TL_PACKET_HEADER_SIZE;
+
+/// IPCC channel CPU1 uses to post MAC 802.15.4 commands and receive their
+/// response on.
+pub(crate) const TL_CHANNEL_MAC_802_15_4_CMD_RSP: u8 = 5;
+/// IPCC channel CPU2 uses to post unsolicited MAC 802.15.4 events on
+/// (data indications, association/disassociation notifications, ...).
+pub(crate) const TL_CHANNEL_MAC_802_15_4_NOTIFICATION: u8 = 6;
+
+#[link_section = "MB_MEM2"]
+pub(crate) static mut MAC_802_15_4_CMD_BUFFER: MaybeUninit<CmdPacket> = MaybeUninit::uninit();
+
+#[allow(dead_code)] // filled in by CPU2, not read back by name
+#[link_section = "MB_MEM2"]
+static mut MAC_802_15_4_NOTIF_BUFFER: MaybeUninit<[u8; TL_PACKET_HEADER_SIZE + crate::TL_EVT_HEADER_SIZE + 255]> =
+    MaybeUninit::uninit();
+
+/// Boxed MAC events (data indications, confirms, association notifications)
+/// drained from [`TL_CHANNEL_MAC_802_15_4_NOTIFICATION`].
+pub(crate) static MAC_EVT_CHANNEL: Channel<CriticalSectionRawMutex, EvtBox, 32> = Channel::new();
+
+/// Handle to the MAC 802.15.4 command/event subsystem on CPU2.
+///
+/// Application code normally doesn't talk to this directly; use
+/// [`crate::mac_driver::MacDriver`] to run a smoltcp/embassy-net IP stack
+/// over it instead.
+pub struct Mac {
+    _private: (),
+}
+
+impl Mac {
+    pub(crate) fn new() -> Self {
+        unsafe {
+            MAC_802_15_4_CMD_BUFFER
+                .as_mut_ptr()
+                .write_volatile(MaybeUninit::zeroed().assume_init());
+            MAC_802_15_4_NOTIF_BUFFER
+                .as_mut_ptr()
+                .write_volatile(MaybeUninit::zeroed().assume_init());
+        }
+
+        Self { _private: () }
+    }
+
+    /// queues a MAC 802.15.4 command (data request, association request, ...)
+    /// for CPU2 over [`TL_CHANNEL_MAC_802_15_4_CMD_RSP`]. The matching
+    /// confirm is reported asynchronously through [`Mac::read`], the same as
+    /// the C TL_MAC driver this mirrors.
+    pub fn write(&self, cmd_code: u16, payload: &[u8]) {
+        assert!(
+            payload.len() <= 255,
+            "MAC command payload does not fit in a TL command buffer"
+        );
+
+        unsafe {
+            let p = MAC_802_15_4_CMD_BUFFER.as_mut_ptr();
+            (*p).cmd_serial.kind = crate::consts::TlPacketType::HciCmd as u8;
+            (*p).cmd_serial.cmd.cmd_code = cmd_code;
+            (*p).cmd_serial.cmd.payload_len = payload.len() as u8;
+
+            let payload_ptr = &mut (*p).cmd_serial.cmd.payload as *mut [u8; 255] as *mut u8;
+            core::ptr::copy_nonoverlapping(payload.as_ptr(), payload_ptr, payload.len());
+        }
+
+        Ipcc::c1_set_tx_channel(TL_CHANNEL_MAC_802_15_4_CMD_RSP, true);
+    }
+
+    /// waits for the next MAC 802.15.4 event drained from
+    /// [`TL_CHANNEL_MAC_802_15_4_NOTIFICATION`] by the IPCC RX interrupt
+    /// handler, e.g. a data indication or an association confirm.
+    ///
+    /// Feed every event this returns into [`crate::mac_driver::MacDriver::on_event`];
+    /// it is the only consumer [`MAC_EVT_CHANNEL`] should have.
+    pub async fn read(&self) -> EvtBox {
+        MAC_EVT_CHANNEL.receive().await
+    }
+}
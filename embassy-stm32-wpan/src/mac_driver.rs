@@ -0,0 +1,183 @@
+//! `embassy-net-driver` adapter over the [`Mac`] subsystem, so a
+//! smoltcp/embassy-net 6LoWPAN/IPv6 stack can run directly over the 802.15.4
+//! radio.
+
+use core::task::Context;
+
+use embassy_net_driver::{Capabilities, Driver, HardwareAddress, LinkState, Medium};
+use embassy_sync::waitqueue::WakerRegistration;
+use heapless::Deque;
+
+use crate::evt::EvtBox;
+use crate::mac::Mac;
+
+/// `aMaxPHYPacketSize`: the largest 802.15.4 PHY frame this driver can hand
+/// to CPU2 in a single MAC data request.
+///
+/// Deliberately *not* 1280: that's the IPv6/6LoWPAN MTU, but it's the
+/// `embassy-net`/smoltcp 6LoWPAN layer above this driver that fragments down
+/// to the radio's real frame size, the same way it would fragment down to an
+/// Ethernet MTU for a wired driver. Advertising the raw PHY size here is what
+/// lets that layer do its job instead of handing `TxToken::consume` frames
+/// [`crate::mac::Mac::write`]'s command buffer can't carry.
+const MTU: usize = 127;
+
+/// Command code for the MAC data request primitive (MCPS-DATA.request),
+/// used to hand an outgoing 802.15.4 frame to CPU2.
+const MAC_DATA_REQUEST_CMD_CODE: u16 = 0x0001;
+
+/// Number of data indications [`MacDriver`] queues ahead of the net stack
+/// polling [`Driver::receive`]. Past this depth, [`MacDriver::on_event`]
+/// drops the oldest queued frame rather than grow without bound.
+const RX_QUEUE_DEPTH: usize = 4;
+
+/// `embassy-net-driver` [`Driver`] implementation over [`Mac`].
+///
+/// Link state is derived from MAC association events fed in through
+/// [`MacDriver::on_event`] rather than polled, and received frame payloads
+/// are handed to smoltcp straight out of the `MemoryManager` buffer pool
+/// backing the boxed event ([`EvtBox::payload`]) instead of being copied
+/// into a separate heap.
+///
+/// [`MacDriver::on_event`] is the *only* consumer of [`Mac::read`]; the task
+/// draining the MAC event queue must feed every event through it rather than
+/// also reading from [`Mac`] directly, or events would race between the two
+/// consumers.
+pub struct MacDriver<'d> {
+    mac: &'d Mac,
+    hw_addr: [u8; 8],
+    link_up: bool,
+    rx: Deque<EvtBox, RX_QUEUE_DEPTH>,
+    waker: WakerRegistration,
+}
+
+impl<'d> MacDriver<'d> {
+    /// `hw_addr` is this device's 802.15.4 extended address, as read from the
+    /// `DeviceInfoTable` at `TlMbox::init` time.
+    pub fn new(mac: &'d Mac, hw_addr: [u8; 8]) -> Self {
+        Self {
+            mac,
+            hw_addr,
+            link_up: false,
+            rx: Deque::new(),
+            waker: WakerRegistration::new(),
+        }
+    }
+
+    /// Feeds a MAC event observed on [`Mac::read`] into the driver, updating
+    /// link state on association/disassociation notifications and queuing
+    /// data indications for [`MacDriver::receive`]. Call this from the task
+    /// draining [`Mac::read`] instead of dispatching events further; this is
+    /// the only path that should be populating this driver's state.
+    pub fn on_event(&mut self, evt: EvtBox) {
+        let stub = evt.stub();
+        let mut changed = false;
+        match stub.evt_code {
+            MLME_ASSOCIATE_CONFIRM | MLME_START_CONFIRM => {
+                changed = !self.link_up;
+                self.link_up = true;
+            }
+            MLME_DISASSOCIATE_INDICATION => {
+                changed = self.link_up;
+                self.link_up = false;
+            }
+            _ => {}
+        }
+
+        if stub.evt_code == MCPS_DATA_INDICATION {
+            if self.rx.is_full() {
+                warn!("MAC RX queue full, dropping oldest queued frame");
+                self.rx.pop_front();
+            }
+            // Capacity was just ensured above, so this cannot fail.
+            let _ = self.rx.push_back(evt);
+            changed = true;
+        }
+
+        if changed {
+            self.waker.wake();
+        }
+    }
+}
+
+/// MCPS-DATA.indication: an incoming 802.15.4 frame.
+const MCPS_DATA_INDICATION: u8 = 0x00;
+/// MLME-ASSOCIATE.confirm: this device joined a PAN.
+const MLME_ASSOCIATE_CONFIRM: u8 = 0x01;
+/// MLME-START.confirm: this device started (and is coordinating) a PAN.
+const MLME_START_CONFIRM: u8 = 0x02;
+/// MLME-DISASSOCIATE.indication: this device left a PAN.
+const MLME_DISASSOCIATE_INDICATION: u8 = 0x03;
+
+impl<'d> Driver for MacDriver<'d> {
+    type RxToken<'a> = MacRxToken where Self: 'a;
+    type TxToken<'a> = MacTxToken<'a> where Self: 'a;
+
+    fn receive(&mut self, cx: &mut Context) -> Option<(Self::RxToken<'_>, Self::TxToken<'_>)> {
+        match self.rx.pop_front() {
+            Some(evt) => Some((MacRxToken(evt), MacTxToken { mac: self.mac })),
+            None => {
+                self.waker.register(cx.waker());
+                None
+            }
+        }
+    }
+
+    fn transmit(&mut self, _cx: &mut Context) -> Option<Self::TxToken<'_>> {
+        Some(MacTxToken { mac: self.mac })
+    }
+
+    fn link_state(&mut self, cx: &mut Context) -> LinkState {
+        self.waker.register(cx.waker());
+        if self.link_up {
+            LinkState::Up
+        } else {
+            LinkState::Down
+        }
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        let mut caps = Capabilities::default();
+        caps.max_transmission_unit = MTU;
+        caps.medium = Medium::Ieee802154;
+        caps
+    }
+
+    fn hardware_address(&self) -> HardwareAddress {
+        HardwareAddress::Ieee802154(self.hw_addr)
+    }
+}
+
+/// Borrows an [`EvtBox`]'s payload in place, straight out of the
+/// `MemoryManager` buffer pool, rather than copying it into a separate
+/// receive heap.
+pub struct MacRxToken(EvtBox);
+
+impl embassy_net_driver::RxToken for MacRxToken {
+    fn consume<R, F: FnOnce(&mut [u8]) -> R>(self, f: F) -> R {
+        let payload = self.0.payload();
+
+        // Safety: `self.0` uniquely owns this event's pool buffer until it is
+        // dropped at the end of this call, so a transient mutable view is
+        // sound; smoltcp only needs it to parse (and in-place decompress)
+        // the frame.
+        let buf = unsafe { core::slice::from_raw_parts_mut(payload.as_ptr() as *mut u8, payload.len()) };
+        f(buf)
+    }
+}
+
+/// Stages an outgoing frame and hands it to CPU2 as a MAC data request.
+pub struct MacTxToken<'d> {
+    mac: &'d Mac,
+}
+
+impl<'d> embassy_net_driver::TxToken for MacTxToken<'d> {
+    fn consume<R, F: FnOnce(&mut [u8]) -> R>(self, len: usize, f: F) -> R {
+        let mut buf = [0u8; MTU];
+        let r = f(&mut buf[..len]);
+
+        self.mac.write(MAC_DATA_REQUEST_CMD_CODE, &buf[..len]);
+
+        r
+    }
+}
@@ -0,0 +1,50 @@
+//! System HCI (SHCI): the opcodes and status codes exchanged over the system
+//! command/response channel ([`crate::sys::Sys`]) for FUS (Firmware Upgrade
+//! Service) control. CPU2 startup configuration opcodes are not defined here.
+
+use core::convert::TryFrom;
+
+/// SHCI command opcodes carried as the `cmd_code` of a system [`crate::cmd::CmdPacket`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u16)]
+pub enum ShciOpcode {
+    /// `SHCI_C2_FUS_GET_STATE`: query the current FUS/wireless-stack state.
+    FusGetState = 0xfc52,
+    /// `SHCI_C2_FUS_FW_UPGRADE`: reflash CPU2 from the image staged in the
+    /// upgrade flash slot.
+    FusFwUpgrade = 0xfc54,
+    /// `SHCI_C2_FUS_FW_DELETE`: erase the currently staged wireless stack.
+    FusFwDelete = 0xfc55,
+    /// `SHCI_C2_FUS_START_WS`: hand control back to the wireless stack once
+    /// FUS has finished.
+    FusStartWs = 0xfc5a,
+}
+
+/// Status word returned in the payload of a FUS command complete event, or
+/// read back via [`ShciOpcode::FusGetState`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ShciFusStatus {
+    Success = 0x00,
+    ImageNotFound = 0x02,
+    ImageCorrupt = 0x03,
+    ImageNotAuthentic = 0x04,
+    ImageNotEncrypted = 0x05,
+    NoFusActivity = 0xff,
+}
+
+impl TryFrom<u8> for ShciFusStatus {
+    type Error = ();
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0x00 => Ok(Self::Success),
+            0x02 => Ok(Self::ImageNotFound),
+            0x03 => Ok(Self::ImageCorrupt),
+            0x04 => Ok(Self::ImageNotAuthentic),
+            0x05 => Ok(Self::ImageNotEncrypted),
+            0xff => Ok(Self::NoFusActivity),
+            _ => Err(()),
+        }
+    }
+}
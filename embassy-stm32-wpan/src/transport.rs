@@ -0,0 +1,95 @@
+//! HCI H4-style transport for bridging the BLE controller to an external host
+//! over a plain UART, framed with [`crate::cobs`] so the link resynchronizes
+//! itself after a dropped byte or a host (re)connecting mid-frame.
+//!
+//! This is what lets a host PC drive the STM32WB BLE stack directly over
+//! HCI ("transparent mode"): [`HostTransport`] drains [`EvtBox`]es from the
+//! controller's event queue and encodes them for the wire, and decodes
+//! frames coming back from the host into [`CmdPacket`]s ready to be queued
+//! for CPU2.
+//!
+//! The wire frame itself is header-less on both directions, matching what
+//! [`EvtBox::write`] serializes and [`crate::cmd::CmdSerial`] deserializes
+//! into: `kind, cmd_code/evt_code, len, payload` (`kind, handle, length,
+//! acl_data` for ACL data). The [`crate::PacketHeader`] every [`CmdPacket`]
+//! carries in front of that is a `LinkedListNode` the IPCC layer threads
+//! through its own free/pending queues — it is never on the wire, so
+//! [`HostTransport::decode_inbound`] leaves room for it rather than decoding
+//! over it.
+
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::channel::Receiver;
+
+use crate::cmd::CmdPacket;
+use crate::cobs;
+use crate::evt::EvtBox;
+
+/// Largest HCI event or ACL data packet this transport will ever have to
+/// frame: a 255-byte payload plus header (3 bytes for an event, 5 for ACL
+/// data).
+const MAX_PACKET_SIZE: usize = 255 + 5;
+
+/// Errors produced while moving bytes across the host link.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TransportError {
+    /// The event could not be serialized (unknown packet kind, or it is
+    /// larger than [`MAX_PACKET_SIZE`]).
+    Event,
+    /// The frame was not a valid COBS encoding, or didn't fit in the
+    /// destination buffer.
+    Cobs,
+    /// The decoded frame is shorter than a command's `kind, cmd_code,
+    /// payload_len` header, or the command buffer cannot even hold a
+    /// [`crate::PacketHeader`].
+    Truncated,
+}
+
+/// Drains the HCI event queue to a host over a COBS-framed byte stream, and
+/// turns frames coming back from the host into queueable [`CmdPacket`]s.
+///
+/// `N` is the capacity of the underlying `EvtBox` channel, i.e. the
+/// `EVT_CHANNEL_CAPACITY` chosen for [`crate::Mailbox`].
+pub struct HostTransport<'a, const N: usize = { crate::DEFAULT_EVT_CHANNEL_CAPACITY }> {
+    events: Receiver<'a, CriticalSectionRawMutex, EvtBox, N>,
+}
+
+impl<'a, const N: usize> HostTransport<'a, N> {
+    pub fn new(events: Receiver<'a, CriticalSectionRawMutex, EvtBox, N>) -> Self {
+        Self { events }
+    }
+
+    /// Waits for the next event destined for the host, and COBS-encodes it
+    /// (including the trailing `0x00` delimiter) into `out`.
+    /// Returns the number of bytes written.
+    pub async fn next_outbound(&self, out: &mut [u8]) -> Result<usize, TransportError> {
+        let evt = self.events.receive().await;
+
+        let mut raw = [0u8; MAX_PACKET_SIZE];
+        let len = evt.size().map_err(|_| TransportError::Event)?;
+        let raw = raw.get_mut(..len).ok_or(TransportError::Event)?;
+        evt.write(raw).map_err(|_| TransportError::Event)?;
+
+        cobs::encode(raw, out).ok_or(TransportError::Cobs)
+    }
+
+    /// Decodes a single COBS frame received from the host (with or without
+    /// its trailing delimiter) into `cmd_buf`, a caller-supplied command
+    /// buffer (e.g. [`crate::SYS_CMD_BUF`] or a BLE command buffer) sized to
+    /// hold a full [`CmdPacket`]. The header-less decoded frame is written
+    /// starting after `cmd_buf`'s [`crate::PacketHeader`], i.e. at its
+    /// `cmd_serial` offset, leaving the header itself for the IPCC layer to
+    /// manage. Returns a pointer to the decoded packet, ready to be handed
+    /// to the HCI command queue.
+    pub fn decode_inbound(&self, frame: &[u8], cmd_buf: &mut [u8]) -> Result<*mut CmdPacket, TransportError> {
+        let header_size = core::mem::size_of::<crate::PacketHeader>();
+        let cmd_serial_buf = cmd_buf.get_mut(header_size..).ok_or(TransportError::Truncated)?;
+
+        let len = cobs::decode(frame, cmd_serial_buf).ok_or(TransportError::Cobs)?;
+
+        if len < crate::TL_CMD_HEADER_SIZE {
+            return Err(TransportError::Truncated);
+        }
+
+        Ok(cmd_buf.as_mut_ptr().cast())
+    }
+}